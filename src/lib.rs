@@ -6,13 +6,22 @@ use std::{
 };
 
 use chrono::{DateTime, NaiveDateTime, Utc};
+use url::Url;
 
 #[cfg(feature = "feature-cookie")]
 mod feature_cookie;
+mod store;
+#[cfg(feature = "serde")]
+mod serde_support;
+
+pub use store::{CookieStore, StoreAction, StoreError};
+#[cfg(feature = "serde")]
+pub use serde_support::{load_json, save_json};
 
 const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cookie {
     pub http_only: bool,
     pub domain: String,
@@ -25,6 +34,7 @@ pub struct Cookie {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CookieExpires {
     Session,
     DateTime(DateTime<Utc>),
@@ -71,82 +81,264 @@ impl From<io::Error> for ParseError {
     }
 }
 
+impl Cookie {
+    pub fn is_expired(&self) -> bool {
+        match self.expires {
+            CookieExpires::Session => false,
+            CookieExpires::DateTime(dt) => dt <= Utc::now(),
+        }
+    }
+
+    pub fn matches_url(&self, url: &Url) -> bool {
+        match url.scheme() {
+            "http" | "https" => {}
+            _ => return false,
+        }
+
+        if self.secure && url.scheme() == "http" {
+            return false;
+        }
+
+        let host = match url.host_str() {
+            Some(host) => host,
+            None => return false,
+        };
+
+        if !domain_matches(host, &self.domain, self.include_subdomains) {
+            return false;
+        }
+
+        path_matches(url.path(), &self.path)
+    }
+}
+
+// RFC 6265 domain matching: exact match, or a subdomain of a leading-dot /
+// `include_subdomains` domain.
+pub(crate) fn domain_matches(host: &str, domain: &str, include_subdomains: bool) -> bool {
+    let include_subdomains = include_subdomains || domain.starts_with('.');
+    let domain = domain.strip_prefix('.').unwrap_or(domain);
+
+    if host.eq_ignore_ascii_case(domain) {
+        return true;
+    }
+
+    if include_subdomains && host.len() > domain.len() {
+        let (rest, suffix) = host.split_at(host.len() - domain.len());
+        return rest.ends_with('.') && suffix.eq_ignore_ascii_case(domain);
+    }
+
+    false
+}
+
+// RFC 6265 path matching: exact match, or the cookie path is a directory
+// prefix of the request path.
+pub(crate) fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    if request_path == cookie_path {
+        return true;
+    }
+
+    if let Some(rest) = request_path.strip_prefix(cookie_path) {
+        if cookie_path.ends_with('/') {
+            return true;
+        }
+
+        if rest.starts_with('/') {
+            return true;
+        }
+    }
+
+    false
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.http_only {
+            write!(f, "{}", HTTP_ONLY_PREFIX)?;
+        }
+
+        let expires = match self.expires {
+            CookieExpires::Session => 0,
+            CookieExpires::DateTime(dt) => dt.timestamp(),
+        };
+
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.domain,
+            bool_to_str(self.include_subdomains),
+            self.path,
+            bool_to_str(self.secure),
+            expires,
+            self.name,
+            self.value
+        )
+    }
+}
+
+fn bool_to_str(b: bool) -> &'static str {
+    if b {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+pub fn to_string(cookies: &[Cookie]) -> String {
+    let mut buf = String::new();
+    for cookie in cookies {
+        buf.push_str(&cookie.to_string());
+        buf.push('\n');
+    }
+    buf
+}
+
+pub fn write<W: io::Write>(cookies: &[Cookie], mut writer: W) -> io::Result<()> {
+    for cookie in cookies {
+        writeln!(writer, "{}", cookie)?;
+    }
+    Ok(())
+}
+
 pub fn parse(bytes: &[u8]) -> Result<Vec<Cookie>, ParseError> {
+    parse_reader(Cursor::new(bytes))
+}
+
+pub fn parse_reader<R: BufRead>(reader: R) -> Result<Vec<Cookie>, ParseError> {
+    CookieReader::new(reader).collect()
+}
+
+// Like `parse`, but a bad line is recorded as `(line_number, ParseError)`
+// instead of aborting the whole parse, so every valid cookie is still
+// recovered from a partially-corrupt file.
+pub fn parse_lenient(bytes: &[u8]) -> (Vec<Cookie>, Vec<(usize, ParseError)>) {
     let mut cursor = Cursor::new(bytes);
     let mut buf = String::new();
 
-    let mut cookies: Vec<Cookie> = vec![];
+    let mut cookies = vec![];
+    let mut errors = vec![];
+    let mut line_no = 0;
 
     loop {
         buf.clear();
-        let n = match cursor.read_line(&mut buf)? {
-            0 => break,
-            1 => continue,
-            n => n - 1,
+        line_no += 1;
+
+        let n = match cursor.read_line(&mut buf) {
+            Ok(0) => break,
+            Ok(1) => continue,
+            Ok(n) => n - 1,
+            Err(err) => {
+                errors.push((line_no, err.into()));
+                break;
+            }
         };
 
-        let mut s = &buf[..n];
-
-        let mut http_only = false;
-        if s.starts_with(HTTP_ONLY_PREFIX) {
-            http_only = true;
-            s = &buf[HTTP_ONLY_PREFIX.len()..n];
-        } else if s.starts_with('#') {
-            continue;
+        match parse_line(&buf[..n]) {
+            Ok(Some(cookie)) => cookies.push(cookie),
+            Ok(None) => {}
+            Err(err) => errors.push((line_no, err)),
         }
+    }
 
-        let mut split = s.split('\t');
-
-        let domain = split.next().ok_or(ParseError::DomainMissing)?;
-
-        let include_subdomains = split.next().ok_or(ParseError::IncludeSubdomainsMissing)?;
-        let include_subdomains: bool = include_subdomains
-            .to_ascii_lowercase()
-            .parse()
-            .map_err(ParseError::IncludeSubdomainsInvalid)?;
-
-        let path = split.next().ok_or(ParseError::PathMissing)?;
-
-        let secure = split.next().ok_or(ParseError::SecureMissing)?;
-        let secure: bool = secure
-            .to_ascii_lowercase()
-            .parse()
-            .map_err(ParseError::SecureInvalid)?;
-
-        let expires = split.next().ok_or(ParseError::ExpiresMissing)?;
-        let expires: u64 = expires.parse().map_err(ParseError::ExpiresInvalid)?;
-        let expires = if expires == 0 {
-            CookieExpires::Session
-        } else {
-            CookieExpires::DateTime(DateTime::<Utc>::from_utc(
-                NaiveDateTime::from_timestamp(expires as i64, 0),
-                Utc,
-            ))
-        };
-
-        let name = split.next().ok_or(ParseError::NameMissing)?;
+    (cookies, errors)
+}
 
-        let value = split.next().ok_or(ParseError::ValueMissing)?;
+// Parses a single already-trimmed line, returning `Ok(None)` for comment
+// lines that should be skipped.
+fn parse_line(s: &str) -> Result<Option<Cookie>, ParseError> {
+    let mut http_only = false;
+    let s = if let Some(rest) = s.strip_prefix(HTTP_ONLY_PREFIX) {
+        http_only = true;
+        rest
+    } else if s.starts_with('#') {
+        return Ok(None);
+    } else {
+        s
+    };
+
+    let mut split = s.split('\t');
+
+    let domain = split.next().ok_or(ParseError::DomainMissing)?;
+
+    let include_subdomains = split.next().ok_or(ParseError::IncludeSubdomainsMissing)?;
+    let include_subdomains: bool = include_subdomains
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(ParseError::IncludeSubdomainsInvalid)?;
+
+    let path = split.next().ok_or(ParseError::PathMissing)?;
+
+    let secure = split.next().ok_or(ParseError::SecureMissing)?;
+    let secure: bool = secure
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(ParseError::SecureInvalid)?;
+
+    let expires = split.next().ok_or(ParseError::ExpiresMissing)?;
+    let expires: u64 = expires.parse().map_err(ParseError::ExpiresInvalid)?;
+    let expires = if expires == 0 {
+        CookieExpires::Session
+    } else {
+        CookieExpires::DateTime(DateTime::<Utc>::from_utc(
+            NaiveDateTime::from_timestamp(expires as i64, 0),
+            Utc,
+        ))
+    };
+
+    let name = split.next().ok_or(ParseError::NameMissing)?;
+
+    let value = split.next().ok_or(ParseError::ValueMissing)?;
+
+    if split.next().is_some() {
+        return Err(ParseError::TooManyElements);
+    }
 
-        if split.next().is_some() {
-            return Err(ParseError::TooManyElements);
-        }
+    Ok(Some(Cookie {
+        http_only,
+        domain: domain.to_owned(),
+        include_subdomains,
+        path: path.to_owned(),
+        secure,
+        expires,
+        name: name.to_owned(),
+        value: value.to_owned(),
+    }))
+}
 
-        let cookie = Cookie {
-            http_only,
-            domain: domain.to_owned(),
-            include_subdomains,
-            path: path.to_owned(),
-            secure,
-            expires,
-            name: name.to_owned(),
-            value: value.to_owned(),
-        };
+// Parses one cookie per `read_line` without buffering the whole file.
+pub struct CookieReader<R> {
+    reader: R,
+    buf: String,
+}
 
-        cookies.push(cookie);
+impl<R: BufRead> CookieReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+        }
     }
+}
 
-    Ok(cookies)
+impl<R: BufRead> Iterator for CookieReader<R> {
+    type Item = Result<Cookie, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buf.clear();
+            let n = match self.reader.read_line(&mut self.buf) {
+                Ok(0) => return None,
+                Ok(1) => continue,
+                Ok(n) => n - 1,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            match parse_line(&self.buf[..n]) {
+                Ok(Some(cookie)) => return Some(Ok(cookie)),
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -182,4 +374,128 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_cookie_reader() -> Result<(), String> {
+        let txt_content = fs::read_to_string("tests/files/demo_cookies.txt").unwrap();
+
+        let cookies = CookieReader::new(txt_content.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+
+        assert_eq!(cookies.len(), 5);
+
+        let cookie = cookies.last().unwrap();
+        assert_eq!(cookie.domain, ".github.com");
+        assert_eq!(cookie.name, "logged_in");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let txt_content = "example.com\tFALSE\t/\tFALSE\t0\tfoo\tbar\n\
+             this-line-is-malformed\n\
+             example.org\tFALSE\t/\tFALSE\t0\tbaz\tqux\n";
+
+        let (cookies, errors) = parse_lenient(txt_content.as_bytes());
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].name, "foo");
+        assert_eq!(cookies[1].name, "baz");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 2);
+        assert_eq!(errors[0].1, ParseError::IncludeSubdomainsMissing);
+    }
+
+    #[test]
+    fn test_to_string_round_trip() -> Result<(), String> {
+        let txt_content = fs::read_to_string("tests/files/demo_cookies.txt").unwrap();
+
+        let cookies = parse(txt_content.as_bytes()).map_err(|err| err.to_string())?;
+
+        let rendered = to_string(&cookies);
+        let cookies_again = parse(rendered.as_bytes()).map_err(|err| err.to_string())?;
+
+        assert_eq!(cookies_again.len(), cookies.len());
+
+        let cookie = cookies_again.last().unwrap();
+        assert_eq!(cookie.http_only, true);
+        assert_eq!(cookie.domain, ".github.com");
+        assert_eq!(cookie.include_subdomains, true);
+        assert_eq!(cookie.path, "/");
+        assert_eq!(cookie.secure, true);
+        match cookie.expires {
+            CookieExpires::Session => assert!(false),
+            CookieExpires::DateTime(dt) => {
+                assert_eq!(dt.naive_utc().timestamp(), 1640586740);
+            }
+        }
+        assert_eq!(cookie.name, "logged_in");
+        assert_eq!(cookie.value, "no");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_url() {
+        let cookie = Cookie {
+            http_only: false,
+            domain: ".example.com".to_owned(),
+            include_subdomains: true,
+            path: "/foo".to_owned(),
+            secure: true,
+            expires: CookieExpires::Session,
+            name: "foo".to_owned(),
+            value: "bar".to_owned(),
+        };
+
+        assert!(cookie.matches_url(&Url::parse("https://www.example.com/foo/bar").unwrap()));
+        assert!(!cookie.matches_url(&Url::parse("http://www.example.com/foo/bar").unwrap()));
+        assert!(!cookie.matches_url(&Url::parse("https://example.org/foo").unwrap()));
+        assert!(!cookie.matches_url(&Url::parse("https://www.example.com/bar").unwrap()));
+    }
+
+    #[test]
+    fn test_matches_url_leading_dot_domain_without_include_subdomains() {
+        // A cookie whose `domain` carries a leading dot but whose
+        // `include_subdomains` flag wasn't set to match (e.g. hand-built,
+        // or recovered via `parse_lenient`/`load_json`) must still match
+        // subdomains per RFC 6265.
+        let cookie = Cookie {
+            http_only: false,
+            domain: ".example.com".to_owned(),
+            include_subdomains: false,
+            path: "/".to_owned(),
+            secure: false,
+            expires: CookieExpires::Session,
+            name: "foo".to_owned(),
+            value: "bar".to_owned(),
+        };
+
+        assert!(cookie.matches_url(&Url::parse("http://www.example.com/").unwrap()));
+        assert!(cookie.matches_url(&Url::parse("http://example.com/").unwrap()));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let mut cookie = Cookie {
+            http_only: false,
+            domain: "example.com".to_owned(),
+            include_subdomains: false,
+            path: "/".to_owned(),
+            secure: false,
+            expires: CookieExpires::Session,
+            name: "foo".to_owned(),
+            value: "bar".to_owned(),
+        };
+        assert!(!cookie.is_expired());
+
+        cookie.expires = CookieExpires::DateTime(DateTime::<Utc>::from_utc(
+            NaiveDateTime::from_timestamp(1, 0),
+            Utc,
+        ));
+        assert!(cookie.is_expired());
+    }
 }
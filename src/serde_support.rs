@@ -0,0 +1,60 @@
+use std::io;
+
+use crate::Cookie;
+
+pub fn save_json<W: io::Write>(cookies: &[Cookie], writer: W) -> serde_json::Result<()> {
+    serde_json::to_writer(writer, cookies)
+}
+
+pub fn load_json<R: io::Read>(reader: R) -> serde_json::Result<Vec<Cookie>> {
+    serde_json::from_reader(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{DateTime, NaiveDateTime, Utc};
+
+    use crate::CookieExpires;
+
+    #[test]
+    fn test_save_load_json_round_trip() {
+        let cookies = vec![
+            Cookie {
+                http_only: true,
+                domain: ".example.com".to_owned(),
+                include_subdomains: true,
+                path: "/".to_owned(),
+                secure: true,
+                expires: CookieExpires::Session,
+                name: "foo".to_owned(),
+                value: "bar".to_owned(),
+            },
+            Cookie {
+                http_only: false,
+                domain: "example.com".to_owned(),
+                include_subdomains: false,
+                path: "/a".to_owned(),
+                secure: false,
+                expires: CookieExpires::DateTime(DateTime::<Utc>::from_utc(
+                    NaiveDateTime::from_timestamp(1640586740, 0),
+                    Utc,
+                )),
+                name: "baz".to_owned(),
+                value: "qux".to_owned(),
+            },
+        ];
+
+        let mut buf = vec![];
+        save_json(&cookies, &mut buf).unwrap();
+
+        let loaded = load_json(buf.as_slice()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].name, "foo");
+        match loaded[1].expires {
+            CookieExpires::DateTime(dt) => assert_eq!(dt.timestamp(), 1640586740),
+            CookieExpires::Session => panic!("expected DateTime"),
+        }
+    }
+}
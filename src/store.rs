@@ -0,0 +1,244 @@
+use std::{collections::HashMap, error, fmt};
+
+use url::Url;
+
+use crate::Cookie;
+
+#[derive(PartialEq, Debug)]
+pub enum StoreAction {
+    Inserted,
+    UpdatedExisting,
+    ExpiredExisting,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum StoreError {
+    PublicSuffixDomain,
+}
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PublicSuffixDomain => write!(f, "PublicSuffixDomain"),
+        }
+    }
+}
+impl error::Error for StoreError {}
+
+// domain -> path -> name -> Cookie
+type CookieMap = HashMap<String, HashMap<String, HashMap<String, Cookie>>>;
+
+#[derive(Debug, Default)]
+pub struct CookieStore {
+    cookies: CookieMap,
+    public_suffixes: Option<Vec<String>>,
+}
+
+impl CookieStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_public_suffixes(public_suffixes: Vec<String>) -> Self {
+        Self {
+            cookies: HashMap::new(),
+            public_suffixes: Some(public_suffixes),
+        }
+    }
+
+    pub fn from_cookies(cookies: Vec<Cookie>) -> Self {
+        let mut store = Self::new();
+        for cookie in cookies {
+            let _ = store.insert(cookie);
+        }
+        store
+    }
+
+    pub fn insert(&mut self, cookie: Cookie) -> Result<StoreAction, StoreError> {
+        if let Some(public_suffixes) = &self.public_suffixes {
+            if is_public_suffix(&cookie.domain, public_suffixes) {
+                return Err(StoreError::PublicSuffixDomain);
+            }
+        }
+
+        // An already-expired cookie never creates a new entry; it only
+        // removes a matching one that was stored previously, pruning any
+        // path/domain map left empty by the removal.
+        if cookie.is_expired() {
+            self.remove(&cookie.domain, &cookie.path, &cookie.name);
+            return Ok(StoreAction::ExpiredExisting);
+        }
+
+        let names = self
+            .cookies
+            .entry(cookie.domain.clone())
+            .or_default()
+            .entry(cookie.path.clone())
+            .or_default();
+
+        let action = if names.insert(cookie.name.clone(), cookie).is_some() {
+            StoreAction::UpdatedExisting
+        } else {
+            StoreAction::Inserted
+        };
+
+        Ok(action)
+    }
+
+    fn remove(&mut self, domain: &str, path: &str, name: &str) {
+        if let Some(paths) = self.cookies.get_mut(domain) {
+            if let Some(names) = paths.get_mut(path) {
+                names.remove(name);
+                if names.is_empty() {
+                    paths.remove(path);
+                }
+            }
+            if paths.is_empty() {
+                self.cookies.remove(domain);
+            }
+        }
+    }
+
+    pub fn matches(&self, url: &Url) -> Vec<&Cookie> {
+        let mut matched: Vec<&Cookie> = self
+            .cookies
+            .values()
+            .flat_map(|paths| paths.values())
+            .flat_map(|names| names.values())
+            .filter(|cookie| !cookie.is_expired() && cookie.matches_url(url))
+            .collect();
+
+        matched.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+
+        matched
+    }
+}
+
+fn is_public_suffix(domain: &str, public_suffixes: &[String]) -> bool {
+    let domain = domain.strip_prefix('.').unwrap_or(domain);
+
+    public_suffixes
+        .iter()
+        .any(|suffix| suffix.eq_ignore_ascii_case(domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::CookieExpires;
+
+    fn make_cookie(domain: &str, path: &str, name: &str) -> Cookie {
+        Cookie {
+            http_only: false,
+            domain: domain.to_owned(),
+            include_subdomains: false,
+            path: path.to_owned(),
+            secure: false,
+            expires: CookieExpires::Session,
+            name: name.to_owned(),
+            value: "v".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_insert_replaces_same_domain_path_name() {
+        let mut store = CookieStore::new();
+
+        assert_eq!(
+            store.insert(make_cookie("example.com", "/", "foo")),
+            Ok(StoreAction::Inserted)
+        );
+        assert_eq!(
+            store.insert(make_cookie("example.com", "/", "foo")),
+            Ok(StoreAction::UpdatedExisting)
+        );
+    }
+
+    #[test]
+    fn test_insert_expired_removes_existing() {
+        let mut store = CookieStore::new();
+        store.insert(make_cookie("example.com", "/", "foo")).unwrap();
+
+        let mut expired = make_cookie("example.com", "/", "foo");
+        expired.expires = CookieExpires::DateTime(chrono::DateTime::<chrono::Utc>::from_utc(
+            chrono::NaiveDateTime::from_timestamp(1, 0),
+            chrono::Utc,
+        ));
+
+        assert_eq!(
+            store.insert(expired),
+            Ok(StoreAction::ExpiredExisting)
+        );
+        assert_eq!(
+            store.matches(&Url::parse("http://example.com/").unwrap()).len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_insert_expired_prunes_empty_maps() {
+        let mut store = CookieStore::new();
+        store.insert(make_cookie("example.com", "/", "foo")).unwrap();
+
+        let mut expired = make_cookie("example.com", "/", "foo");
+        expired.expires = CookieExpires::DateTime(chrono::DateTime::<chrono::Utc>::from_utc(
+            chrono::NaiveDateTime::from_timestamp(1, 0),
+            chrono::Utc,
+        ));
+        store.insert(expired).unwrap();
+
+        assert!(store.cookies.is_empty());
+    }
+
+    #[test]
+    fn test_matches_filters_cookies_expired_since_insertion() {
+        let mut store = CookieStore::new();
+
+        let mut cookie = make_cookie("example.com", "/", "foo");
+        cookie.expires = CookieExpires::DateTime(chrono::DateTime::<chrono::Utc>::from_utc(
+            chrono::NaiveDateTime::from_timestamp(1, 0),
+            chrono::Utc,
+        ));
+        // Insert directly into the index, bypassing `insert`'s own expiry
+        // check, to simulate a cookie that was valid when stored and has
+        // since expired.
+        store
+            .cookies
+            .entry(cookie.domain.clone())
+            .or_default()
+            .entry(cookie.path.clone())
+            .or_default()
+            .insert(cookie.name.clone(), cookie);
+
+        assert_eq!(
+            store
+                .matches(&Url::parse("http://example.com/").unwrap())
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_public_suffix_rejection() {
+        let mut store = CookieStore::with_public_suffixes(vec!["com".to_owned()]);
+
+        assert_eq!(
+            store.insert(make_cookie(".com", "/", "foo")),
+            Err(StoreError::PublicSuffixDomain)
+        );
+    }
+
+    #[test]
+    fn test_matches_most_specific_path_first() {
+        let mut store = CookieStore::new();
+        store.insert(make_cookie("example.com", "/", "a")).unwrap();
+        store
+            .insert(make_cookie("example.com", "/foo", "b"))
+            .unwrap();
+
+        let matched = store.matches(&Url::parse("http://example.com/foo/bar").unwrap());
+        assert_eq!(matched.len(), 2);
+        assert_eq!(matched[0].name, "b");
+        assert_eq!(matched[1].name, "a");
+    }
+}